@@ -0,0 +1,73 @@
+use crate::Model;
+
+/// A single achievement: an id, a human-readable description, and a predicate evaluated
+/// against [`Model`] state once a game ends.
+pub struct Achievement {
+    pub id: &'static str,
+    pub description: &'static str,
+    predicate: fn(&Model) -> bool,
+}
+
+/// Every achievement the game tracks.
+pub const ALL: &[Achievement] = &[
+    Achievement {
+        id: "first_win",
+        description: "First Win",
+        predicate: |model| model.total_wins >= 1,
+    },
+    Achievement {
+        id: "flawless",
+        description: "Flawless",
+        predicate: |model| model.won && !model.placed_incorrect_flag,
+    },
+    Achievement {
+        id: "no_flag_clear",
+        description: "No-Flag Clear",
+        predicate: |model| model.won && !model.used_flag,
+    },
+    Achievement {
+        id: "speed_demon",
+        description: "Speed Demon",
+        predicate: is_speed_demon,
+    },
+    Achievement {
+        id: "big_board",
+        description: "Big Board",
+        predicate: |model| model.won && model.field.rows() >= 30 && model.field.cols() >= 30,
+    },
+];
+
+/// A win finished under this many seconds per [`Cell`] unlocks "Speed Demon", so the threshold
+/// scales with the size of the board instead of favoring small difficulties.
+const SPEED_DEMON_SECS_PER_CELL: f32 = 0.25;
+
+fn is_speed_demon(model: &Model) -> bool {
+    if !model.won {
+        return false;
+    }
+    let cell_count = (model.field.rows() * model.field.cols()) as f32;
+    model.elapsed_secs <= cell_count * SPEED_DEMON_SECS_PER_CELL
+}
+
+/// Checks every achievement not already in `unlocked` against the current `model` state.
+///
+/// # Returns
+///
+/// the ids of achievements newly unlocked by this call, in declaration order.
+pub fn check(model: &Model, unlocked: &[String]) -> Vec<&'static str> {
+    ALL.iter()
+        .filter(|achievement| !unlocked.iter().any(|id| id == achievement.id))
+        .filter(|achievement| (achievement.predicate)(model))
+        .map(|achievement| achievement.id)
+        .collect()
+}
+
+/// # Returns
+///
+/// the description for the achievement with the given `id`, or `"Achievement"` if unknown.
+pub fn description(id: &str) -> &'static str {
+    ALL.iter()
+        .find(|achievement| achievement.id == id)
+        .map(|achievement| achievement.description)
+        .unwrap_or("Achievement")
+}
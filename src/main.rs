@@ -1,11 +1,20 @@
-use std::time::SystemTime;
+use std::time::{Instant, SystemTime};
 use nannou::prelude::*;
 use nannou::winit::event::VirtualKeyCode;
 use nannou_egui::{self, Egui, egui};
 use rand::Rng;
 
+mod scores;
+use scores::ScoreEntry;
+
+mod solver;
+mod achievements;
+
 const DEFAULT_FIELD_ROWS: u32 = 10;
 const DEFAULT_FIELD_COLS: u32 = 10;
+/// The worst-case amount of cells `Field::place_bombs` reserves as safe: the clicked cell plus
+/// its 8 neighbors.
+const MAX_SAFE_ZONE_SIZE: u32 = 9;
 const CELL_COLOR: CellColor = CellColor::new(0.0, 1.0, 0.0);
 const BOMB_COLOR: CellColor = CellColor::new(1.0, 0.0, 0.0);
 const REVEALED_COLOR: CellColor = CellColor::new(0.69, 0.69, 0.69);
@@ -48,16 +57,68 @@ impl From<CellColor> for (f32, f32, f32) {
     }
 }
 
-/// The [`Field`] of the game containing all [`Cell`]s.
+/// The difficulty presets offered in the Settings window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Difficulty {
+    Easy,
+    Medium,
+    Difficult,
+    Custom,
+}
+
+impl Difficulty {
+    const ALL: [Difficulty; 4] = [
+        Difficulty::Easy,
+        Difficulty::Medium,
+        Difficulty::Difficult,
+        Difficulty::Custom,
+    ];
+
+    /// # Returns
+    ///
+    /// the preset `(rows, cols, mine_count)` for this [`Difficulty`], or [`None`] for
+    /// [`Difficulty::Custom`] since it is controlled by the player instead.
+    fn preset(&self) -> Option<(u32, u32, u32)> {
+        match self {
+            Difficulty::Easy => Some((8, 8, 10)),
+            Difficulty::Medium => Some((16, 16, 40)),
+            Difficulty::Difficult => Some((24, 24, 99)),
+            Difficulty::Custom => None,
+        }
+    }
+
+    /// # Returns
+    ///
+    /// the label shown for this [`Difficulty`] in the Settings dropdown.
+    fn label(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy: 8x8, 10 mines",
+            Difficulty::Medium => "Medium: 16x16, 40 mines",
+            Difficulty::Difficult => "Difficult: 24x24, 99 mines",
+            Difficulty::Custom => "Custom",
+        }
+    }
+}
+
+/// The [`Field`] of the game containing all [`Cell`]s, the amount of bombs placed in it and
+/// whether that placement has already happened.
 #[derive(Debug, Clone)]
-struct Field(Vec<Vec<Cell>>);
+struct Field(Vec<Vec<Cell>>, u32, bool);
 
 impl Field {
-    /// Create an empty [`Field`] without any bombs.
+    /// Create an empty [`Field`] without any bombs. Bombs are placed lazily on the first
+    /// [`Field::reveal`] so the first click can never hit one, see [`Field::place_bombs`].
     pub fn empty(rows: u32, cols: u32) -> Self {
         let field = vec![vec![Cell::new(false); cols as usize]; rows as usize];
 
-        Self(field)
+        Self(field, 0, false)
+    }
+
+    /// # Returns
+    ///
+    /// whether bombs have already been placed in the [`Field`].
+    fn bombs_placed(&self) -> bool {
+        self.2
     }
 
     /// Get the [`Cell`] at the given `position`.
@@ -86,14 +147,22 @@ impl Field {
 
     /// # Returns
     ///
-    /// the bomb count calculated by rows and columns
+    /// the amount of bombs placed in the [`Field`].
     fn bomb_count(&self) -> u32 {
-        (self.rows() * self.cols()) / 10
+        self.1
     }
 
-    /// Place the given `bomb_amount` at random points in the [`Field`].
-    pub fn place_bombs(&mut self) {
-        let bomb_count = self.bomb_count();
+    /// Place the given `bomb_count` at random points in the [`Field`], never on `safe_position`
+    /// or one of its neighbors, so the first reveal always opens a region. `bomb_count` is
+    /// capped to the amount of non-safe cells available, so an oversized request can never
+    /// spin the placement loop forever.
+    pub fn place_bombs(&mut self, bomb_count: u32, safe_position: Point2) {
+        let safe_positions = self.get_neighbor_positions(&safe_position);
+        let available_cells = self.rows() * self.cols() - safe_positions.len() as u32 - 1;
+        let bomb_count = bomb_count.min(available_cells);
+
+        self.1 = bomb_count;
+        self.2 = true;
 
         let mut rand_y;
         let mut rand_x;
@@ -102,8 +171,9 @@ impl Field {
             loop {
                 rand_y = rand::thread_rng().gen_range(0..self.rows()) as usize;
                 rand_x = rand::thread_rng().gen_range(0..self.cols()) as usize;
+                let position = Point2::new(rand_x as f32, rand_y as f32);
                 cell = &mut self.0[rand_y][rand_x];
-                if !cell.is_bomb {
+                if !cell.is_bomb && position != safe_position && !safe_positions.contains(&position) {
                     break;
                 }
             }
@@ -289,8 +359,38 @@ impl Field {
                         Point2::new(cell_x_pos + model.cell_width / 2.0, cell_y_pos),
                     );
                 }
+
+                if !cell.is_revealed {
+                    let probability = model
+                        .mine_probabilities
+                        .iter()
+                        .find(|(position, _)| *position == Point2::new(x as f32, y as f32))
+                        .map(|(_, probability)| *probability);
+                    if let Some(probability) = probability {
+                        draw.rect()
+                            .x_y(cell_x_pos, cell_y_pos)
+                            .w_h(model.cell_width, model.cell_height)
+                            .rgba(1.0, 0.0, 0.0, probability * 0.6);
+                    }
+                }
+
+                if model.cursor == Some(Point2::new(x as f32, y as f32)) {
+                    draw.rect()
+                        .x_y(cell_x_pos, cell_y_pos)
+                        .w_h(model.cell_width, model.cell_height)
+                        .no_fill()
+                        .stroke(YELLOW)
+                        .stroke_weight(3.0);
+                }
             }
         }
+        draw.text(&format!("Time: {:.0}s", model.elapsed_secs))
+            .x_y(model.field_margin_x + model.field_width / 2.0, model.field_height + model.field_margin_y * 0.5)
+            .w_h(model.field_width, model.field_margin_y)
+            .font_size((model.cell_width / 2.0) as u32)
+            .align_text_middle_y()
+            .color(BLACK);
+
         if model.won || model.lost {
             let message = if model.won {
                 "Wow! You won OMG MLG"
@@ -304,6 +404,20 @@ impl Field {
                 .align_text_middle_y()
                 .color(BLACK);
         }
+
+        if let Some((message, shown_since)) = &model.toast {
+            const FADE_SECS: f32 = 4.0;
+            let elapsed = shown_since.elapsed().as_secs_f32();
+            if elapsed < FADE_SECS {
+                let alpha = 1.0 - elapsed / FADE_SECS;
+                draw.text(message)
+                    .x_y(model.field_margin_x + model.field_width / 2.0, model.field_height + model.field_margin_y * 2.2)
+                    .w_h(model.field_width, model.field_margin_y)
+                    .font_size((model.cell_width / 2.5) as u32)
+                    .align_text_middle_y()
+                    .rgba(0.0, 0.0, 0.0, alpha);
+            }
+        }
     }
 }
 
@@ -313,8 +427,10 @@ struct Model {
     won: bool,
     lost: bool,
     settings_ready: bool,
+    difficulty: Difficulty,
     field_rows: u32,
     field_cols: u32,
+    mine_count: u32,
     cell_width: f32,
     cell_height: f32,
     field_width: f32,
@@ -323,6 +439,19 @@ struct Model {
     field_margin_y: f32,
     last_left_click: u128,
     last_right_click: u128,
+    timer_start: Option<Instant>,
+    elapsed_secs: f32,
+    best_scores: Vec<ScoreEntry>,
+    show_best_scores: bool,
+    cursor: Option<Point2>,
+    last_key_action: u128,
+    solver_enabled: bool,
+    mine_probabilities: Vec<(Point2, f32)>,
+    total_wins: u32,
+    placed_incorrect_flag: bool,
+    used_flag: bool,
+    unlocked_achievements: Vec<String>,
+    toast: Option<(String, Instant)>,
 }
 
 fn main() {
@@ -341,8 +470,8 @@ fn model(app: &App) -> Model {
         .unwrap();
     let window = app.window(window_id).unwrap();
 
-    let mut field = Field::empty(DEFAULT_FIELD_ROWS, DEFAULT_FIELD_COLS);
-    field.place_bombs();
+    let default_mine_count = (DEFAULT_FIELD_ROWS * DEFAULT_FIELD_COLS) / 10;
+    let field = Field::empty(DEFAULT_FIELD_ROWS, DEFAULT_FIELD_COLS);
 
     let egui = Egui::from_window(&window);
 
@@ -352,8 +481,10 @@ fn model(app: &App) -> Model {
         won: false,
         lost: false,
         settings_ready: false,
+        difficulty: Difficulty::Custom,
         field_rows: DEFAULT_FIELD_ROWS,
         field_cols: DEFAULT_FIELD_COLS,
+        mine_count: default_mine_count,
         cell_width: 0.0,
         cell_height: 0.0,
         field_width: 0.0,
@@ -362,6 +493,19 @@ fn model(app: &App) -> Model {
         field_margin_y: 0.0,
         last_left_click: 0,
         last_right_click: 0,
+        timer_start: None,
+        elapsed_secs: 0.0,
+        best_scores: scores::load_scores(),
+        show_best_scores: false,
+        cursor: None,
+        last_key_action: 0,
+        solver_enabled: false,
+        mine_probabilities: vec![],
+        total_wins: 0,
+        placed_incorrect_flag: false,
+        used_flag: false,
+        unlocked_achievements: scores::load_achievements(),
+        toast: None,
     }
 }
 
@@ -381,16 +525,76 @@ fn update(app: &App, model: &mut Model, _update: Update) {
         let egui = &mut model.egui;
         let ctx = egui.begin_frame();
         egui::Window::new("Settings").show(&ctx, |ui| {
-            ui.add(egui::Slider::new(&mut model.field_rows, 4..=42).text("Rows"));
-            ui.add(egui::Slider::new(&mut model.field_cols, 4..=42).text("Columns"));
+            egui::ComboBox::from_label("Difficulty")
+                .selected_text(model.difficulty.label())
+                .show_ui(ui, |ui| {
+                    for difficulty in Difficulty::ALL {
+                        if ui
+                            .selectable_value(&mut model.difficulty, difficulty, difficulty.label())
+                            .clicked()
+                        {
+                            if let Some((rows, cols, mines)) = difficulty.preset() {
+                                model.field_rows = rows;
+                                model.field_cols = cols;
+                                model.mine_count = mines;
+                            }
+                        }
+                    }
+                });
+
+            if model.difficulty == Difficulty::Custom {
+                ui.add(egui::Slider::new(&mut model.field_rows, 4..=42).text("Rows"));
+                ui.add(egui::Slider::new(&mut model.field_cols, 4..=42).text("Columns"));
+
+                let max_mine_count = (model.field_rows * model.field_cols)
+                    .saturating_sub(MAX_SAFE_ZONE_SIZE)
+                    .max(1);
+                model.mine_count = model.mine_count.clamp(1, max_mine_count);
+                ui.add(egui::Slider::new(&mut model.mine_count, 1..=max_mine_count).text("Mines"));
+            }
+
+            ui.checkbox(&mut model.solver_enabled, "Solver hints (mine probabilities)");
+            ui.label("While playing: H steps one forced move, G auto-plays until stuck.");
 
             if ui.button("Play").clicked() {
                 model.field = Field::empty(model.field_rows, model.field_cols);
-                model.field.place_bombs();
                 model.settings_ready = true;
+                model.timer_start = None;
+                model.elapsed_secs = 0.0;
+                model.cursor = None;
+                model.placed_incorrect_flag = false;
+                model.used_flag = false;
+                model.toast = None;
+                model.mine_probabilities = vec![];
+            }
+
+            if ui
+                .button(if model.show_best_scores { "Hide best scores" } else { "Best scores" })
+                .clicked()
+            {
+                model.show_best_scores = !model.show_best_scores;
             }
         });
+
+        if model.show_best_scores {
+            egui::Window::new("Best scores").show(&ctx, |ui| {
+                if model.best_scores.is_empty() {
+                    ui.label("No times recorded yet.");
+                } else {
+                    for score in &model.best_scores {
+                        ui.label(format!(
+                            "{}x{}, {} mines: {:.0}s",
+                            score.rows, score.cols, score.mine_count, score.elapsed_secs
+                        ));
+                    }
+                }
+            });
+        }
     } else {
+        if let Some(timer_start) = model.timer_start {
+            model.elapsed_secs = timer_start.elapsed().as_secs_f32();
+        }
+
         for button in app.mouse.buttons.pressed() {
             match button {
                 (MouseButton::Left, position) => {
@@ -399,18 +603,7 @@ fn update(app: &App, model: &mut Model, _update: Update) {
 
                     model.last_left_click = time_now;
                     if let Some(position) = mouse_pos_to_field_pos(&position, model, &app.window_rect()) {
-                        if model.field.get(position).has_flag { break; }
-
-                        if model.field.get(position).is_revealed {
-                            model.lost = model.field.reveal_neighbors(position);
-                        } else {
-                            model.lost = model.field.reveal(&position);
-                        }
-                    }
-                    model.won = model.field.check_win();
-
-                    if model.won || model.lost {
-                        model.field.reveal_all();
+                        try_reveal(model, position);
                     }
                 }
                 (MouseButton::Right, position) => {
@@ -420,12 +613,59 @@ fn update(app: &App, model: &mut Model, _update: Update) {
                     model.last_right_click = time_now;
                     if let Some(position) = mouse_pos_to_field_pos(&position, model, &app.window_rect()) {
                         if model.field.get(position).is_revealed { break; }
-                        model.field.toggle_flag(&position);
+                        set_flag(model, position);
                     }
                 }
                 (_, _) => {}
             }
         }
+
+        let time_now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).expect("WHAT THE FUCK?").as_millis();
+        if time_now - model.last_key_action >= 150 {
+            let cursor = model.cursor.unwrap_or(Point2::new(0.0, 0.0));
+            for key in app.keys.down.iter() {
+                match key {
+                    VirtualKeyCode::Left | VirtualKeyCode::A => {
+                        model.cursor = Some(Point2::new((cursor.x - 1.0).max(0.0), cursor.y));
+                        model.last_key_action = time_now;
+                    }
+                    VirtualKeyCode::Right | VirtualKeyCode::D => {
+                        model.cursor = Some(Point2::new((cursor.x + 1.0).min(model.field.cols() as f32 - 1.0), cursor.y));
+                        model.last_key_action = time_now;
+                    }
+                    VirtualKeyCode::Up | VirtualKeyCode::W => {
+                        model.cursor = Some(Point2::new(cursor.x, (cursor.y + 1.0).min(model.field.rows() as f32 - 1.0)));
+                        model.last_key_action = time_now;
+                    }
+                    VirtualKeyCode::Down | VirtualKeyCode::S => {
+                        model.cursor = Some(Point2::new(cursor.x, (cursor.y - 1.0).max(0.0)));
+                        model.last_key_action = time_now;
+                    }
+                    VirtualKeyCode::Space | VirtualKeyCode::Return => {
+                        model.cursor = Some(cursor);
+                        try_reveal(model, cursor);
+                        model.last_key_action = time_now;
+                    }
+                    VirtualKeyCode::F => {
+                        model.cursor = Some(cursor);
+                        if !model.field.get(cursor).is_revealed {
+                            set_flag(model, cursor);
+                        }
+                        model.last_key_action = time_now;
+                    }
+                    VirtualKeyCode::H => {
+                        solver::step(model);
+                        model.last_key_action = time_now;
+                    }
+                    VirtualKeyCode::G => {
+                        solver::auto_play(model);
+                        model.last_key_action = time_now;
+                    }
+                    _ => {}
+                }
+            }
+        }
+
     }
 
     // Calculate Cell and Field sizes and save them
@@ -466,6 +706,113 @@ fn view(app: &App, model: &Model, frame: Frame) {
     }
 }
 
+/// Recomputes `model.mine_probabilities` if the solver is enabled, or clears them otherwise.
+///
+/// Called only after a [`Field`] mutation (a reveal or a flag toggle) instead of every frame,
+/// since [`solver::mine_probabilities`] brute-forces border components and is too expensive to
+/// re-run 60 times a second.
+fn refresh_mine_probabilities(model: &mut Model) {
+    model.mine_probabilities = if model.solver_enabled {
+        solver::mine_probabilities(&model.field)
+    } else {
+        vec![]
+    };
+}
+
+/// # Returns
+///
+/// whether any currently-flagged [`Cell`] on `field` does not hold a bomb.
+fn has_incorrect_flag(field: &Field) -> bool {
+    for y in 0..field.rows() {
+        for x in 0..field.cols() {
+            let cell = field.get(Point2::new(x as f32, y as f32));
+            if cell.has_flag && !cell.is_bomb {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+/// Reveals the [`Cell`] at `position`, updating the win/loss state, the run timer and the
+/// best-scores table the same way a left mouse click does.
+fn try_reveal(model: &mut Model, position: Point2) {
+    if model.field.get(position).has_flag {
+        return;
+    }
+
+    if !model.field.bombs_placed() {
+        model.field.place_bombs(model.mine_count, position);
+        // Flags placed before bombs existed were never judged for correctness; check them now.
+        if has_incorrect_flag(&model.field) {
+            model.placed_incorrect_flag = true;
+        }
+    }
+
+    if model.timer_start.is_none() {
+        model.timer_start = Some(Instant::now());
+    }
+
+    if model.field.get(position).is_revealed {
+        model.lost = model.field.reveal_neighbors(position);
+    } else {
+        model.lost = model.field.reveal(&position);
+    }
+    model.won = model.field.check_win();
+
+    if let Some(timer_start) = model.timer_start {
+        model.elapsed_secs = timer_start.elapsed().as_secs_f32();
+    }
+
+    if model.won || model.lost {
+        model.field.reveal_all();
+    }
+
+    if model.won {
+        model.total_wins += 1;
+        scores::record_score(
+            &mut model.best_scores,
+            ScoreEntry {
+                rows: model.field.rows(),
+                cols: model.field.cols(),
+                mine_count: model.field.bomb_count(),
+                elapsed_secs: model.elapsed_secs,
+            },
+        );
+
+        let newly_unlocked = achievements::check(model, &model.unlocked_achievements);
+        if !newly_unlocked.is_empty() {
+            for id in &newly_unlocked {
+                model.unlocked_achievements.push(id.to_string());
+            }
+            scores::save_achievements(&model.unlocked_achievements);
+
+            let message = newly_unlocked
+                .iter()
+                .map(|id| achievements::description(id))
+                .collect::<Vec<_>>()
+                .join(", ");
+            model.toast = Some((format!("Achievement unlocked: {message}"), Instant::now()));
+        }
+    }
+
+    refresh_mine_probabilities(model);
+}
+
+/// Toggles the flag at `position`, tracking flag usage for achievements: whether any flag was
+/// ever placed this game, and whether an incorrect flag (on a non-bomb cell) was ever placed.
+fn set_flag(model: &mut Model, position: Point2) {
+    model.field.toggle_flag(&position);
+    if model.field.get(position).has_flag {
+        model.used_flag = true;
+        if model.field.bombs_placed() && !model.field.get(position).is_bomb {
+            model.placed_incorrect_flag = true;
+        }
+    }
+
+    refresh_mine_probabilities(model);
+}
+
 /// Converts the position of the mouse to the corresponding field position.
 ///
 /// # Returns
@@ -0,0 +1,108 @@
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+/// The amount of fastest times kept per `(rows, cols, mine_count)` combination.
+const MAX_SCORES_PER_DIFFICULTY: usize = 5;
+
+/// A single completed game recorded in the high-scores table.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ScoreEntry {
+    pub rows: u32,
+    pub cols: u32,
+    pub mine_count: u32,
+    pub elapsed_secs: f32,
+}
+
+/// # Returns
+///
+/// the directory persisted game data (high scores, achievements) is stored in.
+fn config_dir() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("minesweeper_rs")
+}
+
+/// # Returns
+///
+/// the path of the file the high-scores table is persisted to.
+fn scores_file() -> PathBuf {
+    config_dir().join("scores.json")
+}
+
+/// # Returns
+///
+/// the path of the file the unlocked achievement ids are persisted to.
+fn achievements_file() -> PathBuf {
+    config_dir().join("achievements.json")
+}
+
+/// Loads the high-scores table from disk.
+///
+/// # Returns
+///
+/// an empty table if none has been saved yet or it can't be read.
+pub fn load_scores() -> Vec<ScoreEntry> {
+    fs::read_to_string(scores_file())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Records a finished game in `scores` and persists the table to disk, keeping only the
+/// fastest [`MAX_SCORES_PER_DIFFICULTY`] times per `(rows, cols, mine_count)` combination.
+pub fn record_score(scores: &mut Vec<ScoreEntry>, entry: ScoreEntry) {
+    scores.push(entry);
+    scores.sort_by(|a, b| a.elapsed_secs.partial_cmp(&b.elapsed_secs).unwrap());
+
+    let mut kept: Vec<ScoreEntry> = vec![];
+    for score in scores.iter() {
+        let already_kept = kept
+            .iter()
+            .filter(|s| {
+                s.rows == score.rows && s.cols == score.cols && s.mine_count == score.mine_count
+            })
+            .count();
+        if already_kept < MAX_SCORES_PER_DIFFICULTY {
+            kept.push(*score);
+        }
+    }
+    *scores = kept;
+
+    save_scores(scores);
+}
+
+/// Writes the high-scores table to disk, creating the config directory if needed.
+fn save_scores(scores: &[ScoreEntry]) {
+    let path = scores_file();
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    if let Ok(json) = serde_json::to_string_pretty(scores) {
+        let _ = fs::write(path, json);
+    }
+}
+
+/// Loads the ids of unlocked achievements from disk.
+///
+/// # Returns
+///
+/// an empty list if none has been saved yet or it can't be read.
+pub fn load_achievements() -> Vec<String> {
+    fs::read_to_string(achievements_file())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Persists the ids of unlocked achievements to disk, creating the config directory if needed.
+pub fn save_achievements(unlocked: &[String]) {
+    let path = achievements_file();
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    if let Ok(json) = serde_json::to_string_pretty(unlocked) {
+        let _ = fs::write(path, json);
+    }
+}
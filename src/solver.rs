@@ -0,0 +1,289 @@
+use std::collections::{HashMap, HashSet};
+
+use nannou::prelude::*;
+
+use crate::{set_flag, try_reveal, Field, Model};
+
+/// The largest connected component of border [`Cell`]s brute-forced for mine probabilities.
+/// Components larger than this are left untinted instead of enumerating `2^n` assignments.
+const MAX_BRUTE_FORCE_COMPONENT: usize = 20;
+
+/// An integer grid position used as a hash-map/set key, since [`Point2`] is backed by floats
+/// and doesn't implement `Eq`/`Hash`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Pos(i32, i32);
+
+impl From<Point2> for Pos {
+    fn from(point: Point2) -> Self {
+        Self(point.x as i32, point.y as i32)
+    }
+}
+
+impl From<Pos> for Point2 {
+    fn from(pos: Pos) -> Self {
+        Point2::new(pos.0 as f32, pos.1 as f32)
+    }
+}
+
+/// A single deduced move the solver wants applied to the [`Field`].
+#[derive(Debug, Clone, Copy)]
+enum Deduction {
+    /// The [`Cell`] at this position holds no bomb and can be revealed.
+    Safe(Point2),
+    /// The [`Cell`] at this position holds a bomb and should be flagged.
+    Mine(Point2),
+}
+
+/// A constraint derived from one revealed numbered [`Cell`]: exactly `mines` bombs are spread
+/// across `unknown`.
+#[derive(Debug, Clone)]
+struct Constraint {
+    unknown: HashSet<Pos>,
+    mines: u32,
+}
+
+/// Collects one [`Constraint`] per revealed numbered [`Cell`] that still has unrevealed,
+/// unflagged neighbors.
+fn constraints(field: &Field) -> Vec<Constraint> {
+    let mut constraints = vec![];
+    for y in 0..field.rows() {
+        for x in 0..field.cols() {
+            let position = Point2::new(x as f32, y as f32);
+            let cell = field.get(position);
+            if !cell.is_revealed || cell.bomb_count == 0 {
+                continue;
+            }
+
+            let unknown: HashSet<Pos> = field
+                .get_neighbor_positions(&position)
+                .into_iter()
+                .filter(|p| !field.get(*p).is_revealed && !field.get(*p).has_flag)
+                .map(Pos::from)
+                .collect();
+            if unknown.is_empty() {
+                continue;
+            }
+
+            let flagged = field.count_surrounding_flags(&position);
+            constraints.push(Constraint {
+                unknown,
+                mines: cell.bomb_count.saturating_sub(flagged),
+            });
+        }
+    }
+    constraints
+}
+
+/// Runs the single-point rule and the subset-elimination rule once over every [`Constraint`]
+/// and returns every deduction found, deduplicated by position: several constraints routinely
+/// agree on the same cell, and applying the same [`Deduction::Safe`] twice would chord an
+/// already-revealed [`Cell`] instead of being a no-op.
+fn deduce(field: &Field) -> Vec<Deduction> {
+    let mut safe: HashSet<Pos> = HashSet::new();
+    let mut mines: HashSet<Pos> = HashSet::new();
+    let all_constraints = constraints(field);
+
+    for constraint in &all_constraints {
+        if constraint.mines == 0 {
+            safe.extend(constraint.unknown.iter().copied());
+        } else if constraint.mines as usize == constraint.unknown.len() {
+            mines.extend(constraint.unknown.iter().copied());
+        }
+    }
+
+    for a in &all_constraints {
+        for b in &all_constraints {
+            if a.unknown == b.unknown || !a.unknown.is_subset(&b.unknown) {
+                continue;
+            }
+
+            let diff: HashSet<Pos> = b.unknown.difference(&a.unknown).copied().collect();
+            let diff_mines = b.mines as i64 - a.mines as i64;
+            if diff.is_empty() || diff_mines < 0 {
+                continue;
+            }
+            let diff_mines = diff_mines as u32;
+
+            if diff_mines == 0 {
+                safe.extend(diff.iter().copied());
+            } else if diff_mines as usize == diff.len() {
+                mines.extend(diff.iter().copied());
+            }
+        }
+    }
+
+    safe.into_iter()
+        .map(|p| Deduction::Safe(p.into()))
+        .chain(mines.into_iter().map(|p| Deduction::Mine(p.into())))
+        .collect()
+}
+
+/// # Returns
+///
+/// a connected-components partition of `cells`, where two cells share a component iff they
+/// appear together in some [`Constraint`]'s unknown set.
+fn components(cells: &HashSet<Pos>, all_constraints: &[Constraint]) -> Vec<Vec<Pos>> {
+    let mut parent: HashMap<Pos, Pos> = cells.iter().map(|cell| (*cell, *cell)).collect();
+
+    fn find(parent: &mut HashMap<Pos, Pos>, pos: Pos) -> Pos {
+        if parent[&pos] == pos {
+            return pos;
+        }
+        let root = find(parent, parent[&pos]);
+        parent.insert(pos, root);
+        root
+    }
+
+    for constraint in all_constraints {
+        let mut members = constraint.unknown.iter().filter(|p| cells.contains(p));
+        if let Some(&first) = members.next() {
+            for &other in members {
+                let root_a = find(&mut parent, first);
+                let root_b = find(&mut parent, other);
+                if root_a != root_b {
+                    parent.insert(root_a, root_b);
+                }
+            }
+        }
+    }
+
+    let mut groups: HashMap<Pos, Vec<Pos>> = HashMap::new();
+    for &cell in cells {
+        let root = find(&mut parent, cell);
+        groups.entry(root).or_default().push(cell);
+    }
+    groups.into_values().collect()
+}
+
+/// Estimates a per-cell mine probability for every undetermined border [`Cell`] by brute-force
+/// enumerating every mine assignment consistent with the board's constraints, one connected
+/// component of border cells at a time.
+///
+/// # Returns
+///
+/// the `(position, probability)` pairs for every cell a probability could be estimated for.
+/// Components larger than [`MAX_BRUTE_FORCE_COMPONENT`] are skipped.
+pub fn mine_probabilities(field: &Field) -> Vec<(Point2, f32)> {
+    let all_constraints = constraints(field);
+    let deductions = deduce(field);
+    let solved_mines: HashSet<Pos> = deductions
+        .iter()
+        .filter_map(|deduction| match deduction {
+            Deduction::Mine(p) => Some(Pos::from(*p)),
+            Deduction::Safe(_) => None,
+        })
+        .collect();
+    let solved: HashSet<Pos> = deductions
+        .iter()
+        .map(|deduction| match deduction {
+            Deduction::Safe(p) | Deduction::Mine(p) => Pos::from(*p),
+        })
+        .collect();
+
+    let border_cells: HashSet<Pos> = all_constraints
+        .iter()
+        .flat_map(|constraint| constraint.unknown.iter().copied())
+        .filter(|pos| !solved.contains(pos))
+        .collect();
+
+    let mut probabilities = vec![];
+    for component in components(&border_cells, &all_constraints) {
+        if component.is_empty() || component.len() > MAX_BRUTE_FORCE_COMPONENT {
+            continue;
+        }
+
+        let component_set: HashSet<Pos> = component.iter().copied().collect();
+        // Reduce each constraint onto the cells it shares with this component instead of
+        // dropping it outright when it also touches an already-solved cell: the solved mines
+        // it contains still count against its total, so the remaining unknowns stay constrained.
+        let relevant: Vec<Constraint> = all_constraints
+            .iter()
+            .filter_map(|constraint| {
+                let unknown: HashSet<Pos> =
+                    constraint.unknown.intersection(&component_set).copied().collect();
+                if unknown.is_empty() {
+                    return None;
+                }
+                let pinned_mines = constraint
+                    .unknown
+                    .iter()
+                    .filter(|p| solved_mines.contains(p))
+                    .count() as u32;
+                Some(Constraint { unknown, mines: constraint.mines.saturating_sub(pinned_mines) })
+            })
+            .collect();
+
+        let mut mine_counts = vec![0u32; component.len()];
+        let mut consistent_assignments = 0u32;
+
+        for assignment in 0..(1u32 << component.len()) {
+            let is_mine = |i: usize| assignment & (1 << i) != 0;
+            let consistent = relevant.iter().all(|constraint| {
+                let mines_in_constraint = component
+                    .iter()
+                    .enumerate()
+                    .filter(|&(i, pos)| constraint.unknown.contains(pos) && is_mine(i))
+                    .count() as u32;
+                mines_in_constraint == constraint.mines
+            });
+
+            if !consistent {
+                continue;
+            }
+
+            consistent_assignments += 1;
+            for (i, count) in mine_counts.iter_mut().enumerate() {
+                if is_mine(i) {
+                    *count += 1;
+                }
+            }
+        }
+
+        if consistent_assignments == 0 {
+            continue;
+        }
+
+        for (pos, count) in component.into_iter().zip(mine_counts) {
+            probabilities.push((pos.into(), count as f32 / consistent_assignments as f32));
+        }
+    }
+
+    probabilities
+}
+
+/// Applies a single deduction currently found to `model`'s [`Field`]: revealing a deduced-safe
+/// cell or flagging a deduced-mine cell.
+///
+/// # Returns
+///
+/// whether a deduction was applied.
+pub fn step(model: &mut Model) -> bool {
+    if !model.field.bombs_placed() {
+        return false;
+    }
+
+    let deduction = match deduce(&model.field).into_iter().next() {
+        Some(deduction) => deduction,
+        None => return false,
+    };
+
+    match deduction {
+        Deduction::Safe(position) => {
+            if !model.field.get(position).is_revealed {
+                try_reveal(model, position);
+            }
+        }
+        Deduction::Mine(position) => {
+            if !model.field.get(position).has_flag {
+                set_flag(model, position);
+            }
+        }
+    }
+
+    true
+}
+
+/// Repeatedly applies [`step`] until no further forced move can be deduced or the game ends.
+pub fn auto_play(model: &mut Model) {
+    while !model.won && !model.lost && step(model) {}
+}